@@ -0,0 +1,134 @@
+//! Generates a shareable "about my setup" report for bug reports,
+//! mirroring the kind of environment dump tools like the Tauri CLI
+//! ship as an `info` command. Collects the pieces a maintainer always
+//! ends up asking for, so filing a good issue doesn't require digging
+//! through `adb shell getprop` by hand.
+
+use crate::core::sync::{hashset_system_packages, list_all_system_packages, User};
+use crate::core::uad_lists::PackageState;
+use crate::core::utils::{format_diff_time_from_now, last_modified_date, ANDROID_SERIAL, NAME};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Runs `adb shell getprop <prop>` against the current device,
+/// returning an empty string (rather than failing the whole report)
+/// if the property can't be read.
+fn getprop(prop: &str) -> String {
+    Command::new("adb")
+        .args(["shell", "getprop", prop])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+fn adb_version() -> String {
+    Command::new("adb")
+        .arg("version")
+        .output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .unwrap_or_else(|_| "not found".to_string())
+}
+
+/// Runs `adb shell getenforce`, the actual live SELinux enforcement
+/// mode (`Enforcing`/`Permissive`/`Disabled`) — as opposed to the
+/// `ro.build.selinux` prop, which is a legacy build-time flag that
+/// doesn't reflect current enforcement status.
+fn selinux_status() -> String {
+    let status = Command::new("adb")
+        .args(["shell", "getenforce"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+
+    if status.is_empty() {
+        "unknown".to_string()
+    } else {
+        status
+    }
+}
+
+/// Checks both that `adbd` itself runs as root (userdebug/eng builds)
+/// and that `su` grants root (Magisk and friends, where adbd stays
+/// unprivileged but an explicit `su` call escalates) — checking only
+/// the former reports "unrooted" on most Magisk-rooted phones.
+fn root_status() -> &'static str {
+    let is_root_uid = |output: std::process::Output| {
+        String::from_utf8_lossy(&output.stdout).trim() == "0"
+    };
+
+    let adbd_is_root = Command::new("adb")
+        .args(["shell", "id", "-u"])
+        .output()
+        .is_ok_and(is_root_uid);
+
+    let su_grants_root = Command::new("adb")
+        .args(["shell", "su", "-c", "id -u"])
+        .output()
+        .is_ok_and(is_root_uid);
+
+    if adbd_is_root || su_grants_root {
+        "rooted"
+    } else {
+        "unrooted"
+    }
+}
+
+/// Produces a Markdown report with environment, device, and package
+/// counts, suitable for pasting straight into a GitHub issue.
+#[must_use]
+pub fn generate_report(user: Option<&User>, uad_lists_path: PathBuf) -> String {
+    let enabled = hashset_system_packages(PackageState::Enabled, user).len();
+    let disabled = hashset_system_packages(PackageState::Disabled, user).len();
+    let total = list_all_system_packages(user).lines().count();
+    let uninstalled = total.saturating_sub(enabled + disabled);
+
+    let uad_lists_age = format_diff_time_from_now(last_modified_date(uad_lists_path));
+
+    format!(
+        "\
+## Environment
+- {NAME} version: {}
+- OS: {} ({})
+- uad_lists last updated: {uad_lists_age}
+
+## Device
+- `ANDROID_SERIAL`: {}
+- `adb version`: {adb_version}
+- `ro.build.version.release`: {android_release}
+- `ro.product.model`: {model}
+- SELinux: {selinux}
+- Root: {root}
+
+## Packages
+- Enabled: {enabled}
+- Disabled: {disabled}
+- Uninstalled: {uninstalled}
+",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::var(ANDROID_SERIAL).unwrap_or_else(|_| "(unset)".to_string()),
+        android_release = getprop("ro.build.version.release"),
+        model = getprop("ro.product.model"),
+        selinux = selinux_status(),
+        root = root_status(),
+        adb_version = adb_version(),
+    )
+}
+
+/// Writes a generated report under `dir` (normally `setup_uad_dir`'s
+/// result) so it can be attached to an issue or reveal-in-folder'd via
+/// `open_url`. The GUI can also offer copying `generate_report`'s
+/// output straight to the clipboard instead.
+pub fn write_report(report: &str, dir: PathBuf) -> Result<PathBuf, String> {
+    let path = dir.join(format!("{NAME}_diagnostics_{}.md", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+    fs::write(&path, report).map_err(|err| err.to_string())?;
+    Ok(path)
+}
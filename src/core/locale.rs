@@ -0,0 +1,333 @@
+//! Runtime-selectable message catalogs for user-facing strings.
+//!
+//! Catalogs are plain keyed TOML files embedded at compile time under
+//! `locales/<lang>.toml` and loaded once into a [`OnceLock`]. Lookups go
+//! through the [`tr!`] macro, which falls back to the English catalog
+//! whenever the active locale is missing a key (or wasn't loaded at all).
+
+use include_dir::{include_dir, Dir};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+static LOCALES_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/locales");
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// CLDR cardinal plural categories.
+///
+/// English only ever produces [`One`](PluralCategory::One) and
+/// [`Other`](PluralCategory::Other), but other languages (Polish,
+/// Arabic, ...) need the full set, so catalog entries for pluralized
+/// strings carry a variant per category that the target language uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Message {
+    Plain(String),
+    Plural(HashMap<PluralCategory, String>),
+}
+
+/// A loaded language catalog, plus the English one kept around as a
+/// fallback for missing keys.
+pub struct Catalog {
+    lang: String,
+    messages: HashMap<String, Message>,
+    fallback: HashMap<String, Message>,
+}
+
+impl Catalog {
+    /// Loads the catalog for `lang`, falling back to English for any
+    /// key it doesn't define. Returns the English-only catalog when
+    /// `lang` has no matching `locales/<lang>.toml` file.
+    fn load(lang: &str) -> Self {
+        let fallback = Self::parse("en");
+        let messages = if lang == "en" {
+            HashMap::new()
+        } else {
+            Self::parse(lang)
+        };
+
+        Self {
+            lang: lang.to_string(),
+            messages,
+            fallback,
+        }
+    }
+
+    fn parse(lang: &str) -> HashMap<String, Message> {
+        let Some(file) = LOCALES_DIR.get_file(format!("{lang}.toml")) else {
+            return HashMap::new();
+        };
+        let Some(contents) = file.contents_utf8() else {
+            error!("[LOCALE] {lang}.toml is not valid UTF-8");
+            return HashMap::new();
+        };
+
+        parse_str(contents).unwrap_or_else(|e| {
+            error!("[LOCALE] failed to parse {lang}.toml: {e}");
+            HashMap::new()
+        })
+    }
+
+    fn message(&self, key: &str) -> Option<&Message> {
+        self.messages.get(key).or_else(|| self.fallback.get(key))
+    }
+}
+
+/// Parses a catalog's TOML contents into dotted-key messages, e.g. the
+/// `no_description` key of `[package]` becomes `"package.no_description"`
+/// and the `one`/`other` keys of `[time.minutes]` become the variants of
+/// a single `"time.minutes"` pluralized message — matching the nested
+/// `[section.subsection]` tables the catalogs are written with, and the
+/// dotted keys every `tr!()` call site looks up.
+fn parse_str(contents: &str) -> Result<HashMap<String, Message>, toml::de::Error> {
+    let parsed: toml::Value = contents.parse()?;
+    let mut out = HashMap::new();
+    flatten("", &parsed, &mut out);
+    Ok(out)
+}
+
+/// A table counts as a single pluralized message (rather than a nested
+/// section to keep flattening) when every key is a plural category name
+/// and every value is a string.
+fn flatten(prefix: &str, value: &toml::Value, out: &mut HashMap<String, Message>) {
+    match value {
+        toml::Value::String(s) => {
+            out.insert(prefix.to_string(), Message::Plain(s.clone()));
+        }
+        toml::Value::Table(table) => {
+            let is_plural_table = !table.is_empty()
+                && table
+                    .iter()
+                    .all(|(k, v)| parse_category(k).is_some() && v.as_str().is_some());
+
+            if is_plural_table {
+                let variants = table
+                    .iter()
+                    .filter_map(|(category, text)| {
+                        Some((parse_category(category)?, text.as_str()?.to_string()))
+                    })
+                    .collect();
+                out.insert(prefix.to_string(), Message::Plural(variants));
+            } else {
+                for (key, value) in table {
+                    let dotted = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    flatten(&dotted, value, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_category(s: &str) -> Option<PluralCategory> {
+    Some(match s {
+        "zero" => PluralCategory::Zero,
+        "one" => PluralCategory::One,
+        "few" => PluralCategory::Few,
+        "many" => PluralCategory::Many,
+        "other" => PluralCategory::Other,
+        _ => return None,
+    })
+}
+
+/// Selects the CLDR plural category `n` falls into for `lang`.
+///
+/// Only the Polish and Russian/Ukrainian rules are implemented besides
+/// English; any other `lang` degenerates to the English one/other
+/// split, which is also the correct rule for many languages.
+#[must_use]
+pub fn plural_category(lang: &str, n: i64) -> PluralCategory {
+    let n = n.abs();
+    let mod10 = n % 10;
+    let mod100 = n % 100;
+
+    match lang {
+        // Polish: "one" is exactly n == 1, unlike the East Slavic
+        // languages below.
+        "pl" => {
+            if n == 1 {
+                PluralCategory::One
+            } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+        // Russian/Ukrainian: "one" is any n ending in 1 except those
+        // ending in 11 (21, 31, 101, ... are "one", not just n == 1).
+        "ru" | "uk" => {
+            if mod10 == 1 && mod100 != 11 {
+                PluralCategory::One
+            } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+        _ => {
+            if n == 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+    }
+}
+
+/// Loads the catalog for `lang` (e.g. from the `locale` setting) as the
+/// active one. Subsequent [`tr!`] calls use it until the process exits;
+/// there is no reload, since the setting only changes on restart.
+pub fn init(lang: &str) {
+    if CATALOG.set(Catalog::load(lang)).is_err() {
+        error!("[LOCALE] catalog already initialized, ignoring {lang}");
+    }
+}
+
+fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| Catalog::load("en"))
+}
+
+/// Looks up `key` in the active catalog, returning the key itself
+/// (surrounded by `[]`) if it's missing from both the active locale and
+/// the English fallback, so untranslated strings are obvious instead of
+/// silently blank.
+#[must_use]
+pub fn lookup(key: &str) -> String {
+    match catalog().message(key) {
+        Some(Message::Plain(s)) => s.clone(),
+        Some(Message::Plural(_)) => {
+            error!("[LOCALE] `{key}` is a pluralized message, use `tr!({key}, n)`");
+            format!("[{key}]")
+        }
+        None => format!("[{key}]"),
+    }
+}
+
+/// Looks up the pluralized message `key`, selecting the variant for
+/// `n` per the active locale's plural rule and substituting `{n}`.
+#[must_use]
+pub fn lookup_plural(key: &str, n: i64) -> String {
+    let category = plural_category(&catalog().lang, n);
+    let template = match catalog().message(key) {
+        Some(Message::Plural(variants)) => variants
+            .get(&category)
+            .or_else(|| variants.get(&PluralCategory::Other))
+            .cloned(),
+        Some(Message::Plain(s)) => Some(s.clone()),
+        None => None,
+    };
+
+    template.map_or_else(|| format!("[{key}]"), |t| t.replace("{n}", &n.to_string()))
+}
+
+/// Resolves a message key against the active locale catalog, falling
+/// back to English, then to the bracketed key itself.
+///
+/// `tr!("key")` looks up a plain string; `tr!("key", n)` looks up a
+/// pluralized one and picks the right cardinal variant for `n`.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::core::locale::lookup($key)
+    };
+    ($key:expr, $n:expr) => {
+        $crate::core::locale::lookup_plural($key, i64::from($n))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flattens_nested_sections_into_dotted_keys() {
+        let catalog = parse_str(include_str!("../../locales/en.toml")).unwrap();
+
+        assert!(matches!(
+            catalog.get("package.no_description"),
+            Some(Message::Plain(_))
+        ));
+        assert!(matches!(
+            catalog.get("export.header_name"),
+            Some(Message::Plain(_))
+        ));
+        assert!(matches!(
+            catalog.get("theme.dark"),
+            Some(Message::Plain(_))
+        ));
+    }
+
+    #[test]
+    fn flattens_a_plural_table_into_one_entry_with_variants() {
+        let catalog = parse_str(include_str!("../../locales/en.toml")).unwrap();
+
+        let Some(Message::Plural(variants)) = catalog.get("time.minutes") else {
+            panic!("time.minutes should be a pluralized message");
+        };
+        assert_eq!(variants.get(&PluralCategory::One).unwrap(), "{n} min ago");
+        assert_eq!(variants.get(&PluralCategory::Other).unwrap(), "{n} mins ago");
+    }
+
+    #[test]
+    fn looks_up_real_catalog_entries_end_to_end() {
+        let catalog = Catalog {
+            lang: "en".to_string(),
+            messages: HashMap::new(),
+            fallback: parse_str(include_str!("../../locales/en.toml")).unwrap(),
+        };
+
+        assert_eq!(
+            catalog.message("package.no_description"),
+            Some(&Message::Plain(
+                "[No description]: CONTRIBUTION WELCOMED".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn plural_category_polish_only_treats_exact_one_as_one() {
+        assert_eq!(plural_category("pl", 1), PluralCategory::One);
+        assert_eq!(plural_category("pl", 21), PluralCategory::Many);
+        assert_eq!(plural_category("pl", 2), PluralCategory::Few);
+        assert_eq!(plural_category("pl", 5), PluralCategory::Many);
+        assert_eq!(plural_category("pl", 12), PluralCategory::Many);
+    }
+
+    #[test]
+    fn plural_category_russian_treats_21_31_101_as_one() {
+        assert_eq!(plural_category("ru", 1), PluralCategory::One);
+        assert_eq!(plural_category("ru", 21), PluralCategory::One);
+        assert_eq!(plural_category("ru", 31), PluralCategory::One);
+        assert_eq!(plural_category("ru", 101), PluralCategory::One);
+        assert_eq!(plural_category("ru", 11), PluralCategory::Many);
+        assert_eq!(plural_category("ru", 2), PluralCategory::Few);
+        assert_eq!(plural_category("ru", 22), PluralCategory::Few);
+        assert_eq!(plural_category("ru", 5), PluralCategory::Many);
+    }
+
+    #[test]
+    fn plural_category_ukrainian_matches_russian_rule() {
+        assert_eq!(plural_category("uk", 21), PluralCategory::One);
+        assert_eq!(plural_category("uk", 11), PluralCategory::Many);
+        assert_eq!(plural_category("uk", 3), PluralCategory::Few);
+    }
+
+    #[test]
+    fn plural_category_default_rule_is_one_other() {
+        assert_eq!(plural_category("en", 1), PluralCategory::One);
+        assert_eq!(plural_category("en", 0), PluralCategory::Other);
+        assert_eq!(plural_category("en", 21), PluralCategory::Other);
+    }
+}
@@ -0,0 +1,225 @@
+//! Full package-state snapshots, as opposed to the name-only dump
+//! `export_packages` writes. A snapshot captures every package's
+//! `PackageState` for a `User`, so a later `diff_snapshot` against the
+//! live device can compute exactly which packages need to be
+//! re-enabled, disabled, uninstalled, or reinstalled to converge back
+//! to it — the missing piece that turns a CSV dump into an actual
+//! restore path.
+
+use crate::core::sync::{hashset_system_packages, list_all_system_packages, User};
+use crate::core::uad_lists::PackageState;
+use crate::core::utils::{generate_backup_name_ext, ExportFormat};
+use std::fs;
+use std::path::PathBuf;
+
+/// A single package's recorded state at snapshot time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    pub name: String,
+    pub state: PackageState,
+}
+
+/// A point-in-time capture of a `User`'s package states.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// An action needed to bring a package on the live device back in
+/// line with a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotAction {
+    Enable(String),
+    Disable(String),
+    /// Present on the device but should be per-user-uninstalled, i.e.
+    /// what `adb shell pm uninstall --user 0` produces.
+    Uninstall(String),
+    Reinstall(String),
+}
+
+impl Snapshot {
+    /// Captures the current state of every system package for `user`,
+    /// the same way `fetch_packages` classifies them.
+    #[must_use]
+    pub fn capture(user: Option<&User>) -> Self {
+        let enabled = hashset_system_packages(PackageState::Enabled, user);
+        let disabled = hashset_system_packages(PackageState::Disabled, user);
+
+        let entries = list_all_system_packages(user)
+            .lines()
+            .map(|name| {
+                let state = if enabled.contains(name) {
+                    PackageState::Enabled
+                } else if disabled.contains(name) {
+                    PackageState::Disabled
+                } else {
+                    PackageState::Uninstalled
+                };
+                SnapshotEntry {
+                    name: name.to_string(),
+                    state,
+                }
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Writes the snapshot as a `package_name,state` CSV under `dir`,
+    /// reusing `generate_backup_name_ext` with the `.snapshot.csv`
+    /// naming so it sorts next to regular backups but isn't mistaken
+    /// for one.
+    pub fn write(&self, dir: PathBuf) -> Result<PathBuf, String> {
+        let path = dir.join(generate_backup_name_ext(chrono::Local::now(), ExportFormat::Csv))
+            .with_extension("snapshot.csv");
+
+        let file = fs::File::create(&path).map_err(|err| err.to_string())?;
+        let mut wtr = csv::Writer::from_writer(file);
+
+        wtr.write_record(["Package Name", "State"])
+            .map_err(|err| err.to_string())?;
+        for entry in &self.entries {
+            wtr.write_record([&entry.name, &format!("{:?}", entry.state)])
+                .map_err(|err| err.to_string())?;
+        }
+        wtr.flush().map_err(|err| err.to_string())?;
+
+        Ok(path)
+    }
+
+    /// Loads a snapshot previously written by `write`.
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let mut rdr = csv::Reader::from_path(path).map_err(|err| err.to_string())?;
+
+        let entries = rdr
+            .records()
+            .map(|record| {
+                let record = record.map_err(|err| err.to_string())?;
+                let name = record.get(0).unwrap_or_default().to_string();
+                let state = match record.get(1).unwrap_or_default() {
+                    "Enabled" => PackageState::Enabled,
+                    "Disabled" => PackageState::Disabled,
+                    _ => PackageState::Uninstalled,
+                };
+                Ok(SnapshotEntry { name, state })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self { entries })
+    }
+}
+
+/// Picks the action (if any) needed to bring `name` from `current`
+/// state to `target` state. `current` is `None` when the package is
+/// absent from `list_all_system_packages` entirely (fully removed from
+/// the system partition), as opposed to `Some(PackageState::Uninstalled)`
+/// which means it's merely per-user-uninstalled but still present.
+///
+/// Split out from `diff_snapshot` purely so the state-convergence
+/// table can be unit-tested without a device.
+fn convergence_action(
+    name: &str,
+    target: PackageState,
+    current: Option<PackageState>,
+) -> Option<SnapshotAction> {
+    let Some(current) = current else {
+        // Nothing to enable/disable until `pm install-existing` puts
+        // the package back; skip only if the snapshot itself never
+        // expected it to be there either.
+        return (target != PackageState::Uninstalled)
+            .then(|| SnapshotAction::Reinstall(name.to_string()));
+    };
+
+    if current == target {
+        return None;
+    }
+
+    Some(match target {
+        PackageState::Enabled => SnapshotAction::Enable(name.to_string()),
+        PackageState::Disabled => SnapshotAction::Disable(name.to_string()),
+        PackageState::Uninstalled => SnapshotAction::Uninstall(name.to_string()),
+    })
+}
+
+/// Compares `snapshot` against the live device state for `user` and
+/// returns the actions needed to converge the device back to it.
+/// Packages the snapshot doesn't mention (newly appeared since it was
+/// taken) are left untouched.
+#[must_use]
+pub fn diff_snapshot(snapshot: &Snapshot, user: Option<&User>) -> Vec<SnapshotAction> {
+    let present: std::collections::HashSet<&str> =
+        list_all_system_packages(user).lines().collect();
+    let live_enabled = hashset_system_packages(PackageState::Enabled, user);
+    let live_disabled = hashset_system_packages(PackageState::Disabled, user);
+
+    snapshot
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let current = present.contains(entry.name.as_str()).then(|| {
+                if live_enabled.contains(entry.name.as_str()) {
+                    PackageState::Enabled
+                } else if live_disabled.contains(entry.name.as_str()) {
+                    PackageState::Disabled
+                } else {
+                    PackageState::Uninstalled
+                }
+            });
+
+            convergence_action(&entry.name, entry.state, current)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converges_to_enabled() {
+        assert_eq!(
+            convergence_action("com.foo", PackageState::Enabled, Some(PackageState::Disabled)),
+            Some(SnapshotAction::Enable("com.foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn converges_to_disabled() {
+        assert_eq!(
+            convergence_action("com.foo", PackageState::Disabled, Some(PackageState::Enabled)),
+            Some(SnapshotAction::Disable("com.foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn converges_to_uninstalled_when_present() {
+        assert_eq!(
+            convergence_action("com.foo", PackageState::Uninstalled, Some(PackageState::Enabled)),
+            Some(SnapshotAction::Uninstall("com.foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn reinstalls_when_absent_and_snapshot_expected_it_installed() {
+        assert_eq!(
+            convergence_action("com.foo", PackageState::Enabled, None),
+            Some(SnapshotAction::Reinstall("com.foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn no_action_when_absent_and_snapshot_expected_it_uninstalled() {
+        assert_eq!(
+            convergence_action("com.foo", PackageState::Uninstalled, None),
+            None
+        );
+    }
+
+    #[test]
+    fn no_action_when_already_converged() {
+        assert_eq!(
+            convergence_action("com.foo", PackageState::Enabled, Some(PackageState::Enabled)),
+            None
+        );
+    }
+}
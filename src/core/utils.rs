@@ -2,6 +2,7 @@ use crate::core::sync::{hashset_system_packages, list_all_system_packages, User}
 use crate::core::theme::Theme;
 use crate::core::uad_lists::{PackageHashMap, PackageState, Removal, UadList};
 use crate::gui::widgets::package_row::PackageRow;
+use crate::tr;
 use chrono::{offset::Utc, DateTime};
 use csv::Writer;
 use std::path::PathBuf;
@@ -27,7 +28,36 @@ where
     T: chrono::TimeZone,
     T::Offset: std::fmt::Display,
 {
-    format!("uninstalled_packages_{}.csv", t.format("%Y%m%d"))
+    generate_backup_name_ext(t, ExportFormat::Csv)
+}
+
+/// Same as `generate_backup_name`, but with the extension driven by
+/// the chosen `ExportFormat` instead of being hardcoded to `.csv`.
+pub fn generate_backup_name_ext<T>(t: DateTime<T>, format: ExportFormat) -> String
+where
+    T: chrono::TimeZone,
+    T::Offset: std::fmt::Display,
+{
+    format!("uninstalled_packages_{}.{}", t.format("%Y%m%d"), format.extension())
+}
+
+/// File format for package/selection exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    PlainText,
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::PlainText => "txt",
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -44,10 +74,11 @@ pub fn fetch_packages(uad_lists: &PackageHashMap, user_id: Option<&User>) -> Vec
     let mut state;
     let mut removal;
     let mut user_package: Vec<PackageRow> = Vec::new();
+    let no_description = tr!("package.no_description");
 
     for p_name in all_system_packages.lines() {
         state = PackageState::Uninstalled;
-        description = "[No description]: CONTRIBUTION WELCOMED";
+        description = no_description.as_str();
         uad_list = UadList::Unlisted;
         removal = Removal::Unlisted;
 
@@ -73,6 +104,11 @@ pub fn fetch_packages(uad_lists: &PackageHashMap, user_id: Option<&User>) -> Vec
     user_package
 }
 
+// The setting is persisted and compared against these English literals
+// regardless of the active locale: matching on the *translated*
+// display string instead would lose the user's theme the moment they
+// switch locales, since the string saved under locale A would no
+// longer match anything once `tr!` resolves to locale B's text.
 pub fn string_to_theme(theme: &str) -> Theme {
     match theme {
         "Dark" => Theme::Dark,
@@ -95,13 +131,13 @@ pub fn setup_uad_dir(dir: &PathBuf) -> PathBuf {
 
 pub fn open_url(dir: PathBuf) {
     #[cfg(target_os = "windows")]
-    let output = Command::new("explorer").args([dir]).output();
+    let output = launch_external("explorer", [dir]);
 
     #[cfg(target_os = "macos")]
-    let output = Command::new("open").args([dir]).output();
+    let output = launch_external("open", [dir]);
 
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    let output = Command::new("xdg-open").args([dir]).output();
+    let output = launch_external("xdg-open", [dir]);
 
     match output {
         Ok(o) => {
@@ -114,6 +150,85 @@ pub fn open_url(dir: PathBuf) {
     }
 }
 
+/// Environment variables whose bundle-prepended entries need
+/// stripping back down to the host's before spawning an external
+/// program (see [`normalize_pathlist`]).
+const SANDBOX_PATHLIST_VARS: [&str; 5] = [
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "XDG_DATA_DIRS",
+    "XDG_CONFIG_DIRS",
+    "GST_PLUGIN_SYSTEM_PATH",
+];
+
+/// Whether this process is running inside a Flatpak, Snap, or AppImage
+/// bundle, which all prepend their own library/plugin paths onto the
+/// inherited environment before launching UAD-ng.
+fn is_sandboxed() -> bool {
+    PathBuf::from("/.flatpak-info").exists()
+        || std::env::var_os("SNAP").is_some()
+        || std::env::var_os("APPIMAGE").is_some()
+        || std::env::var_os("APPDIR").is_some()
+}
+
+/// Restores `var_name` to what the host environment had before the
+/// bundle prepended its own entries onto it.
+///
+/// Bundle launchers save the pre-bundle value as `<var_name>_ORIG`.
+/// This concatenates that original value with the current one
+/// (original first), drops empty entries, and deduplicates in favor of
+/// the *first* occurrence, i.e. the host/original one rather than
+/// whatever the bundle injected. The variable is unset entirely if the
+/// result is empty.
+fn normalize_pathlist(var_name: &str) {
+    let original = std::env::var_os(format!("{var_name}_ORIG"));
+    let current = std::env::var_os(var_name);
+
+    let mut seen = std::collections::HashSet::new();
+    let entries: Vec<PathBuf> = original
+        .iter()
+        .chain(current.iter())
+        .flat_map(std::env::split_paths)
+        .filter(|p| !p.as_os_str().is_empty())
+        .filter(|p| seen.insert(p.clone()))
+        .collect();
+
+    if entries.is_empty() {
+        std::env::remove_var(var_name);
+    } else if let Ok(joined) = std::env::join_paths(&entries) {
+        std::env::set_var(var_name, joined);
+    }
+}
+
+/// Spawns `program` with a host-clean environment when running inside
+/// a sandboxed bundle (Flatpak/Snap/AppImage), so it doesn't inherit
+/// the bundle's `PATH`/library/plugin paths and fail to start or load
+/// the wrong libraries. Under Flatpak the command is routed through
+/// `flatpak-spawn --host` so it actually runs on the host.
+fn launch_external<I, S>(program: &str, args: I) -> std::io::Result<std::process::Output>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    if !is_sandboxed() {
+        return Command::new(program).args(args).output();
+    }
+
+    for var in SANDBOX_PATHLIST_VARS {
+        normalize_pathlist(var);
+    }
+
+    if PathBuf::from("/.flatpak-info").exists() {
+        Command::new("flatpak-spawn")
+            .arg("--host")
+            .arg(program)
+            .args(args)
+            .output()
+    } else {
+        Command::new(program).args(args).output()
+    }
+}
+
 #[rustfmt::skip]
 #[allow(clippy::option_if_let_else)]
 pub fn last_modified_date(file: PathBuf) -> DateTime<Utc> {
@@ -128,29 +243,53 @@ pub fn format_diff_time_from_now(date: DateTime<Utc>) -> String {
     let last_update = now - date;
     if last_update.num_days() == 0 {
         if last_update.num_hours() == 0 {
-            last_update.num_minutes().to_string() + " min(s) ago"
+            tr!("time.minutes", last_update.num_minutes())
         } else {
-            last_update.num_hours().to_string() + " hour(s) ago"
+            tr!("time.hours", last_update.num_hours())
         }
     } else {
-        last_update.num_days().to_string() + " day(s) ago"
+        tr!("time.days", last_update.num_days())
     }
 }
 
-/// Export selected packages.
-/// File will be saved in same directory where UAD-ng is located.
-pub async fn export_selection(packages: Vec<PackageRow>) -> Result<bool, String> {
-    let selected = packages
+/// Export selected packages under `dir` (normally `setup_uad_dir`'s
+/// result, or a user-chosen folder from `open_folder`), in the given
+/// `format`. Returns the written file's path so the caller can offer
+/// to reveal it via `open_url`.
+pub async fn export_selection(
+    packages: Vec<PackageRow>,
+    dir: PathBuf,
+    format: ExportFormat,
+) -> Result<PathBuf, String> {
+    let selected: Vec<String> = packages
         .iter()
         .filter(|p| p.selected)
         .map(|p| p.name.clone())
-        .collect::<Vec<String>>()
-        .join("\n");
+        .collect();
 
-    match fs::write(EXPORT_FILE_NAME, selected) {
-        Ok(()) => Ok(true),
-        Err(err) => Err(err.to_string()),
-    }
+    let file_stem = EXPORT_FILE_NAME.trim_end_matches(".txt");
+    let path = dir.join(format!("{file_stem}.{}", format.extension()));
+
+    let contents = match format {
+        ExportFormat::PlainText => selected.join("\n"),
+        ExportFormat::Csv => {
+            let mut wtr = Writer::from_writer(vec![]);
+            wtr.write_record([tr!("export.header_name")])
+                .map_err(|err| err.to_string())?;
+            for name in &selected {
+                wtr.write_record([name]).map_err(|err| err.to_string())?;
+            }
+            let bytes = wtr.into_inner().map_err(|err| err.to_string())?;
+            String::from_utf8(bytes).map_err(|err| err.to_string())?
+        }
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(&selected).map_err(|err| err.to_string())?
+        }
+    };
+
+    fs::write(&path, contents).map_err(|err| err.to_string())?;
+
+    Ok(path)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -188,33 +327,135 @@ pub async fn open_folder() -> Result<PathBuf, Error> {
     Ok(picked_folder.path().to_owned())
 }
 
-/// Export uninstalled packages in a csv file.
-/// Exported information will contain package name and description.
+/// Lets the user pick a previously exported selection (`.txt`) or
+/// backup (`.csv`) file to restore from.
+pub async fn open_selection_file() -> Result<PathBuf, Error> {
+    let picked_file = rfd::AsyncFileDialog::new()
+        .add_filter("selection", &["txt", "csv"])
+        .pick_file()
+        .await
+        .ok_or(Error::DialogClosed)?;
+
+    Ok(picked_file.path().to_owned())
+}
+
+/// Loads package names back from a `PlainText` file written by
+/// `export_selection`, i.e. one package name per line. Every non-empty
+/// line is taken as a name; the `Csv` and `Json` exports are not parsed.
+pub async fn import_selection(path: PathBuf) -> Result<Vec<String>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Loads package names back from a CSV file written by
+/// `export_packages`, i.e. reads the `Package Name` column and ignores
+/// the rest.
+pub fn restore_from_csv(path: PathBuf) -> Result<Vec<String>, String> {
+    let mut rdr = csv::Reader::from_path(path).map_err(|err| err.to_string())?;
+
+    rdr.records()
+        .map(|record| {
+            record
+                .map_err(|err| err.to_string())
+                .map(|r| r.get(0).unwrap_or_default().to_string())
+        })
+        .collect()
+}
+
+/// Marks every `PackageRow` in `packages` whose name is in `names` as
+/// selected. Names with no matching package on the current device are
+/// silently skipped. Returns `(matched, skipped)`.
+pub fn apply_imported_selection(packages: &mut [PackageRow], names: &[String]) -> (usize, usize) {
+    // Dedupe first: a hand-edited or concatenated selection file can
+    // repeat a name, and counting each repeat as its own match would
+    // inflate `matched` past the number of packages actually selected.
+    let unique_names: std::collections::HashSet<&str> =
+        names.iter().map(String::as_str).collect();
+    let mut matched = 0;
+
+    for name in &unique_names {
+        if let Some(row) = packages.iter_mut().find(|p| p.name == *name) {
+            row.selected = true;
+            matched += 1;
+        }
+    }
+
+    (matched, unique_names.len() - matched)
+}
+
+/// Export uninstalled packages under `dir` (normally `setup_uad_dir`'s
+/// result, or a user-chosen folder from `open_folder`), in the given
+/// `format`. `Csv` keeps the original name+description dump of
+/// uninstalled packages; `PlainText` writes the same data as one
+/// `name - description` line per package, with no CSV quoting or
+/// header; `Json` covers every package of the user, not only the
+/// uninstalled ones, and additionally captures state, list, removal
+/// info and the user index, so it can later be used as a real backup.
+/// Returns the written file's path.
 pub async fn export_packages(
     user: User,
     phone_packages: Vec<Vec<PackageRow>>,
-) -> Result<bool, String> {
-    let backup_file = generate_backup_name(chrono::Local::now());
-
-    let file = fs::File::create(backup_file).map_err(|err| err.to_string())?;
-    let mut wtr = Writer::from_writer(file);
-
-    wtr.write_record(["Package Name", "Description"])
-        .map_err(|err| err.to_string())?;
+    dir: PathBuf,
+    format: ExportFormat,
+) -> Result<PathBuf, String> {
+    let path = dir.join(generate_backup_name_ext(chrono::Local::now(), format));
 
     let uninstalled_packages: Vec<&PackageRow> = phone_packages[user.index]
         .iter()
         .filter(|p| p.state == PackageState::Uninstalled)
         .collect();
 
-    for package in uninstalled_packages {
-        wtr.write_record([&package.name, &package.description.replace('\n', " ")])
-            .map_err(|err| err.to_string())?;
-    }
+    match format {
+        ExportFormat::Json => {
+            let entries: Vec<_> = phone_packages[user.index]
+                .iter()
+                .map(|p| {
+                    serde_json::json!({
+                        "name": p.name,
+                        "state": format!("{:?}", p.state),
+                        "list": format!("{:?}", p.uad_list),
+                        "removal": format!("{:?}", p.removal),
+                        "description": p.description,
+                        "user_index": user.index,
+                    })
+                })
+                .collect();
+
+            let contents = serde_json::to_string_pretty(&entries).map_err(|err| err.to_string())?;
+            fs::write(&path, contents).map_err(|err| err.to_string())?;
+        }
+        ExportFormat::Csv => {
+            let file = fs::File::create(&path).map_err(|err| err.to_string())?;
+            let mut wtr = Writer::from_writer(file);
 
-    wtr.flush().map_err(|err| err.to_string())?;
+            wtr.write_record([tr!("export.header_name"), tr!("export.header_description")])
+                .map_err(|err| err.to_string())?;
+
+            for package in uninstalled_packages {
+                wtr.write_record([&package.name, &package.description.replace('\n', " ")])
+                    .map_err(|err| err.to_string())?;
+            }
+
+            wtr.flush().map_err(|err| err.to_string())?;
+        }
+        ExportFormat::PlainText => {
+            let contents = uninstalled_packages
+                .iter()
+                .map(|p| format!("{} - {}", p.name, p.description.replace('\n', " ")))
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            fs::write(&path, contents).map_err(|err| err.to_string())?;
+        }
+    }
 
-    Ok(true)
+    Ok(path)
 }
 
 #[cfg(test)]
@@ -231,4 +472,123 @@ mod tests {
             "uninstalled_packages_19700101.csv".to_string()
         );
     }
+
+    #[test]
+    fn backup_name_ext() {
+        assert_eq!(
+            generate_backup_name_ext(
+                chrono::Utc.timestamp_millis_opt(0).unwrap(),
+                ExportFormat::Json
+            ),
+            "uninstalled_packages_19700101.json".to_string()
+        );
+    }
+
+    // Each test below uses its own `_ORIG`-suffixed variable name so
+    // they don't race with each other or with real sandbox vars.
+
+    #[test]
+    fn normalize_pathlist_prepends_original_before_bundle_entries() {
+        let var = "UAD_TEST_PATHLIST_ORDER";
+        std::env::set_var(format!("{var}_ORIG"), "/host/bin");
+        std::env::set_var(var, "/bundle/bin:/host/bin");
+
+        normalize_pathlist(var);
+
+        assert_eq!(std::env::var(var).unwrap(), "/host/bin:/bundle/bin");
+    }
+
+    #[test]
+    fn normalize_pathlist_dedupes_keeping_first_occurrence() {
+        let var = "UAD_TEST_PATHLIST_DEDUP";
+        std::env::set_var(format!("{var}_ORIG"), "/host/bin:/usr/bin");
+        std::env::set_var(var, "/usr/bin:/bundle/bin");
+
+        normalize_pathlist(var);
+
+        assert_eq!(std::env::var(var).unwrap(), "/host/bin:/usr/bin:/bundle/bin");
+    }
+
+    #[test]
+    fn normalize_pathlist_drops_empty_entries() {
+        let var = "UAD_TEST_PATHLIST_EMPTY";
+        std::env::set_var(format!("{var}_ORIG"), "/host/bin::/usr/bin");
+        std::env::remove_var(var);
+
+        normalize_pathlist(var);
+
+        assert_eq!(std::env::var(var).unwrap(), "/host/bin:/usr/bin");
+    }
+
+    #[test]
+    fn normalize_pathlist_unsets_when_result_is_empty() {
+        let var = "UAD_TEST_PATHLIST_UNSET";
+        std::env::remove_var(format!("{var}_ORIG"));
+        std::env::remove_var(var);
+
+        normalize_pathlist(var);
+
+        assert!(std::env::var_os(var).is_none());
+    }
+
+    fn test_row(name: &str) -> PackageRow {
+        PackageRow::new(
+            name,
+            PackageState::Uninstalled,
+            "",
+            UadList::Unlisted,
+            Removal::Unlisted,
+            false,
+            false,
+        )
+    }
+
+    #[test]
+    fn apply_imported_selection_selects_matching_rows() {
+        let mut packages = vec![test_row("com.foo"), test_row("com.bar")];
+        let names = vec!["com.foo".to_string()];
+
+        let (matched, skipped) = apply_imported_selection(&mut packages, &names);
+
+        assert_eq!((matched, skipped), (1, 0));
+        assert!(packages[0].selected);
+        assert!(!packages[1].selected);
+    }
+
+    #[test]
+    fn apply_imported_selection_skips_unknown_names() {
+        let mut packages = vec![test_row("com.foo")];
+        let names = vec!["com.foo".to_string(), "com.unknown".to_string()];
+
+        let (matched, skipped) = apply_imported_selection(&mut packages, &names);
+
+        assert_eq!((matched, skipped), (1, 1));
+    }
+
+    #[test]
+    fn apply_imported_selection_handles_no_matches() {
+        let mut packages = vec![test_row("com.foo")];
+        let names = vec!["com.unknown".to_string()];
+
+        let (matched, skipped) = apply_imported_selection(&mut packages, &names);
+
+        assert_eq!((matched, skipped), (0, 1));
+    }
+
+    #[test]
+    fn apply_imported_selection_dedupes_repeated_names() {
+        let mut packages = vec![test_row("com.foo"), test_row("com.bar")];
+        let names = vec![
+            "com.foo".to_string(),
+            "com.foo".to_string(),
+            "com.unknown".to_string(),
+            "com.unknown".to_string(),
+        ];
+
+        let (matched, skipped) = apply_imported_selection(&mut packages, &names);
+
+        assert_eq!((matched, skipped), (1, 1));
+        assert!(packages[0].selected);
+        assert!(!packages[1].selected);
+    }
 }